@@ -0,0 +1,138 @@
+use crate::config::Config;
+use crate::error::ApiError;
+use crate::{OK_RESPONSE, POOL};
+use bcrypt::{hash, verify, DEFAULT_COST};
+use jsonwebtoken::{decode, encode, DecodingKey, EncodingKey, Header, Validation};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+
+static CONFIG: Lazy<Config> = Lazy::new(Config::init);
+
+// JWT claims: subject is the authenticated user's id
+#[derive(Serialize, Deserialize)]
+pub struct TokenClaims {
+    pub sub: i32,
+    pub iat: i64,
+    pub exp: i64,
+}
+
+#[derive(Deserialize)]
+struct RegisterSchema {
+    name: String,
+    email: String,
+    password: String,
+}
+
+#[derive(Deserialize)]
+struct LoginSchema {
+    email: String,
+    password: String,
+}
+
+#[derive(Serialize)]
+struct AuthResponse {
+    token: String,
+}
+
+// deserialize a request schema from the request body, same convention as get_user_request_body
+fn get_request_body<'a, T: Deserialize<'a>>(request: &'a str) -> Result<T, serde_json::Error> {
+    serde_json::from_str(request.split("\r\n\r\n").last().unwrap_or_default())
+}
+
+pub fn hash_password(password: &str) -> Result<String, bcrypt::BcryptError> {
+    hash(password, DEFAULT_COST)
+}
+
+pub fn create_jwt(user_id: i32) -> Result<String, jsonwebtoken::errors::Error> {
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .expect("system clock before unix epoch")
+        .as_secs() as i64;
+    let exp = now + CONFIG.jwt_maxage * 60;
+
+    let claims = TokenClaims {
+        sub: user_id,
+        iat: now,
+        exp,
+    };
+
+    encode(
+        &Header::default(),
+        &claims,
+        &EncodingKey::from_secret(CONFIG.jwt_secret.as_bytes()),
+    )
+}
+
+// verify a bearer token, returning the authenticated user id
+pub fn verify_jwt(token: &str) -> Result<i32, jsonwebtoken::errors::Error> {
+    let data = decode::<TokenClaims>(
+        token,
+        &DecodingKey::from_secret(CONFIG.jwt_secret.as_bytes()),
+        &Validation::default(),
+    )?;
+    Ok(data.claims.sub)
+}
+
+// pull the bearer token out of the raw "Authorization: Bearer <token>" header line
+fn get_bearer_token(request: &str) -> Option<&str> {
+    request.lines().find_map(|line| {
+        line.strip_prefix("Authorization:")
+            .or_else(|| line.strip_prefix("authorization:"))
+            .and_then(|value| value.trim().strip_prefix("Bearer "))
+    })
+}
+
+// authenticate a request, returning the caller's user id on a valid, unexpired token
+pub fn authenticate_request(request: &str) -> Option<i32> {
+    get_bearer_token(request).and_then(|token| verify_jwt(token).ok())
+}
+
+// handle POST /auth/register
+pub fn handle_register_request(request: &str) -> Result<(String, String), ApiError> {
+    let body = get_request_body::<RegisterSchema>(request)
+        .map_err(|_| ApiError::BadRequest("invalid request body".to_string()))?;
+    crate::validation::validate_name_email(&body.name, &body.email).map_err(ApiError::Validation)?;
+
+    let password_hash = hash_password(&body.password).map_err(|_| ApiError::Database)?;
+
+    let mut client = POOL.get()?;
+    let row = client.query_one(
+        "INSERT INTO users (name, email, password) VALUES ($1, $2, $3) RETURNING id, name, email, password, attributes",
+        &[&body.name, &body.email, &password_hash],
+    )?;
+    let created = crate::User {
+        id: row.get(0),
+        name: row.get(1),
+        email: row.get(2),
+        password: row.get(3),
+        attributes: row.get(4),
+    };
+    Ok((
+        OK_RESPONSE.to_string(),
+        serde_json::to_string(&created).unwrap_or_else(|_| "{}".to_string()),
+    ))
+}
+
+// handle POST /auth/login
+pub fn handle_login_request(request: &str) -> Result<(String, String), ApiError> {
+    let body = get_request_body::<LoginSchema>(request)
+        .map_err(|_| ApiError::BadRequest("invalid request body".to_string()))?;
+
+    let mut client = POOL.get()?;
+    let row = client
+        .query_opt("SELECT id, password FROM users WHERE email = $1", &[&body.email])?
+        .ok_or(ApiError::Unauthorized)?;
+
+    let id: i32 = row.get(0);
+    let password_hash: String = row.get(1);
+
+    if !verify(&body.password, &password_hash).map_err(|_| ApiError::Database)? {
+        return Err(ApiError::Unauthorized);
+    }
+
+    let token = create_jwt(id).map_err(|_| ApiError::Database)?;
+    Ok((
+        OK_RESPONSE.to_string(),
+        serde_json::to_string(&AuthResponse { token }).unwrap_or_else(|_| "{}".to_string()),
+    ))
+}
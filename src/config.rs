@@ -0,0 +1,22 @@
+use std::env;
+
+// JWT configuration, read from the environment; auth.rs holds it in a Lazy, so it is
+// read once, on the first token created or verified
+pub struct Config {
+    pub jwt_secret: String,
+    pub jwt_maxage: i64,
+}
+
+impl Config {
+    pub fn init() -> Config {
+        let jwt_secret = env::var("JWT_SECRET").expect("JWT_SECRET must be set");
+        let jwt_maxage = env::var("JWT_MAXAGE").expect("JWT_MAXAGE must be set");
+
+        Config {
+            jwt_secret,
+            jwt_maxage: jwt_maxage
+                .parse::<i64>()
+                .expect("JWT_MAXAGE must be an integer"),
+        }
+    }
+}
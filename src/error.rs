@@ -0,0 +1,104 @@
+use thiserror::Error;
+
+// all handler failures funnel through this enum so handle_client has one place
+// that maps a failure to an HTTP status line and a JSON body
+#[derive(Error, Debug)]
+pub enum ApiError {
+    #[error("resource not found")]
+    NotFound,
+    #[error("resource already exists")]
+    Conflict,
+    #[error("{0}")]
+    BadRequest(String),
+    #[error("validation failed")]
+    Validation(Vec<String>),
+    #[error("database error")]
+    Database,
+    #[error("unauthorized")]
+    Unauthorized,
+    #[error("forbidden")]
+    Forbidden,
+    #[error("payload too large")]
+    PayloadTooLarge,
+}
+
+impl ApiError {
+    pub fn into_response(self) -> (String, String) {
+        let status_line = match &self {
+            ApiError::NotFound => "HTTP/1.1 404 NOT FOUND",
+            ApiError::Conflict => "HTTP/1.1 409 CONFLICT",
+            ApiError::BadRequest(_) => "HTTP/1.1 400 BAD REQUEST",
+            ApiError::Validation(_) => "HTTP/1.1 422 UNPROCESSABLE ENTITY",
+            ApiError::Database => "HTTP/1.1 500 INTERNAL ERROR",
+            ApiError::Unauthorized => "HTTP/1.1 401 UNAUTHORIZED",
+            ApiError::Forbidden => "HTTP/1.1 403 FORBIDDEN",
+            ApiError::PayloadTooLarge => "HTTP/1.1 413 PAYLOAD TOO LARGE",
+        };
+
+        let error_code = match &self {
+            ApiError::NotFound => "not_found",
+            ApiError::Conflict => "conflict",
+            ApiError::BadRequest(_) => "bad_request",
+            ApiError::Validation(_) => "validation",
+            ApiError::Database => "internal_error",
+            ApiError::Unauthorized => "unauthorized",
+            ApiError::Forbidden => "forbidden",
+            ApiError::PayloadTooLarge => "payload_too_large",
+        };
+
+        let body = if let ApiError::Validation(errors) = &self {
+            serde_json::json!({ "error": error_code, "errors": errors })
+        } else {
+            serde_json::json!({ "error": error_code, "message": self.to_string() })
+        };
+
+        (
+            format!("{status_line}\r\nContent-Type: application/json\r\n\r\n"),
+            body.to_string(),
+        )
+    }
+}
+
+// detect the Postgres unique-violation SQLSTATE and surface it as a 409 instead of a 500
+impl From<postgres::Error> for ApiError {
+    fn from(err: postgres::Error) -> Self {
+        if err.code() == Some(&postgres::error::SqlState::UNIQUE_VIOLATION) {
+            ApiError::Conflict
+        } else {
+            ApiError::Database
+        }
+    }
+}
+
+impl From<r2d2::Error> for ApiError {
+    fn from(_: r2d2::Error) -> Self {
+        ApiError::Database
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn not_found_maps_to_404() {
+        let (status_line, body) = ApiError::NotFound.into_response();
+        assert!(status_line.starts_with("HTTP/1.1 404 NOT FOUND"));
+        assert!(body.contains("\"error\":\"not_found\""));
+    }
+
+    #[test]
+    fn validation_errors_are_reported_as_a_list() {
+        let (status_line, body) =
+            ApiError::Validation(vec!["name must be between 1 and 50 characters".to_string()])
+                .into_response();
+        assert!(status_line.starts_with("HTTP/1.1 422 UNPROCESSABLE ENTITY"));
+        assert!(body.contains("\"errors\":[\"name must be between 1 and 50 characters\"]"));
+    }
+
+    #[test]
+    fn bad_request_carries_its_message_through() {
+        let (_, body) = ApiError::BadRequest("invalid id".to_string()).into_response();
+        assert!(body.contains("\"message\":\"invalid id\""));
+    }
+}
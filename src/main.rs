@@ -1,29 +1,94 @@
 use postgres::{Client, NoTls};
+use postgres::types::ToSql;
 use postgres::Error as PostgresError;
+use r2d2::Pool;
+use r2d2_postgres::PostgresConnectionManager;
 use std::net::{TcpListener, TcpStream};
 use std::io::{Read, Write};
 use std::env;
+use std::thread;
 use once_cell::sync::Lazy;
 use serde::{Deserialize, Serialize};
-use serde_json;
 
-// model: User struct with id, name, email
+mod auth;
+mod config;
+mod error;
+mod rbac;
+mod validation;
+
+use error::ApiError;
+use validation::Validate;
+
+// model: User struct with id, name, email, password, and free-form attributes
 #[derive(Serialize, Deserialize)]
 struct User {
     id: Option<i32>,
     name: String,
     email: String,
+    // never echo the password hash back to a client
+    #[serde(skip_serializing)]
+    password: String,
+    // free-form metadata (preferences, profile fields, etc.); defaults to null when absent
+    #[serde(default)]
+    attributes: serde_json::Value,
 }
 
+// PUT /users/{id} body: like User, but password is optional so a client can
+// update name/email/attributes without resending (and resetting) the password
+#[derive(Deserialize)]
+struct UserUpdate {
+    name: String,
+    email: String,
+    #[serde(default)]
+    password: Option<String>,
+    // absent when the field is omitted, so an update that doesn't mention
+    // attributes leaves the stored value alone instead of nulling it out
+    #[serde(default)]
+    attributes: Option<serde_json::Value>,
+}
+
+type DbPool = Pool<PostgresConnectionManager<NoTls>>;
+type DbConn = r2d2::PooledConnection<PostgresConnectionManager<NoTls>>;
+
 // database URL (runtime-initialized "global")
 static DB_URL: Lazy<String> = Lazy::new(|| {
     env::var("DATABASE_URL").expect("DATABASE_URL must be set")
 });
 
+// connection pool, sized via DB_POOL_SIZE (defaults to 16)
+static POOL: Lazy<DbPool> = Lazy::new(|| {
+    let manager = PostgresConnectionManager::new(
+        DB_URL.as_str().parse().expect("invalid DATABASE_URL"),
+        NoTls,
+    );
+    let pool_size = env::var("DB_POOL_SIZE")
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(16);
+    Pool::builder()
+        .max_size(pool_size)
+        .build(manager)
+        .expect("failed to build connection pool")
+});
+
 // constants
 const OK_RESPONSE: &str = "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\n\r\n";
-const NOT_FOUND: &str = "HTTP/1.1 404 NOT FOUND\r\n\r\n";
-const INTERNAL_ERROR: &str = "HTTP/1.1 500 INTERNAL ERROR\r\n\r\n";
+
+// GET /users?limit=&offset=&sort=&order=&name=&email= pagination defaults/bounds
+const DEFAULT_LIMIT: i64 = 20;
+const MAX_LIMIT: i64 = 100;
+const SORTABLE_COLUMNS: [&str; 3] = ["id", "name", "email"];
+
+// bail out of header parsing if the client never sends a terminator
+const MAX_HEADER_BYTES: usize = 8 * 1024;
+
+// max request body size, configurable via MAX_BODY_BYTES (defaults to 1 MiB)
+static MAX_BODY_BYTES: Lazy<usize> = Lazy::new(|| {
+    env::var("MAX_BODY_BYTES")
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(1024 * 1024)
+});
 
 fn main() {
     // set database
@@ -32,13 +97,20 @@ fn main() {
         return;
     }
 
+    // seed default roles/permissions and the bootstrap admin account
+    rbac::bootstrap();
+
     // start server and print port
     let listener = TcpListener::bind("0.0.0.0:8080").expect("bind 0.0.0.0:8080");
     println!("Server listening on port 8080");
 
+    // hand each connection to its own thread so one slow client can't stall the rest;
+    // the r2d2 pool bounds how many of those threads can hold a db connection at once
     for stream in listener.incoming() {
         match stream {
-            Ok(stream) => handle_client(stream),
+            Ok(stream) => {
+                thread::spawn(move || handle_client(stream));
+            }
             Err(e) => eprintln!("Unable to accept connection: {e}"),
         }
     }
@@ -46,128 +118,298 @@ fn main() {
 
 // handle requests
 fn handle_client(mut stream: TcpStream) {
-    let mut buffer = [0; 4096];
-    let mut request = String::new();
-
-    match stream.read(&mut buffer) {
-        Ok(size) => {
-            request.push_str(String::from_utf8_lossy(&buffer[..size]).as_ref());
-
-            let (status_line, content) = match &*request {
-                r if r.starts_with("POST /users") => handle_post_request(r),
-                r if r.starts_with("GET /users/") => handle_get_request(r),
-                r if r.starts_with("GET /users") => handle_get_all_request(r),
-                r if r.starts_with("PUT /users/") => handle_put_request(r),
-                r if r.starts_with("DELETE /users/") => handle_delete_request(r),
-                _ => (NOT_FOUND.to_string(), "404 not found".to_string()),
-            };
-
-            if let Err(e) = stream.write_all(format!("{}{}", status_line, content).as_bytes()) {
-                eprintln!("Unable to write response: {e}");
-            }
+    let (status_line, content) = match read_full_request(&mut stream) {
+        Ok(request) => match route_request(&request) {
+            Ok(response) => response,
+            Err(e) => e.into_response(),
+        },
+        Err(e) => e.into_response(),
+    };
+
+    if let Err(e) = stream.write_all(format!("{}{}", status_line, content).as_bytes()) {
+        eprintln!("Unable to write response: {e}");
+    }
+}
+
+// read headers up to the "\r\n\r\n" terminator, then keep reading until the full
+// Content-Length body has arrived, so requests split across TCP segments aren't truncated
+fn read_full_request(stream: &mut TcpStream) -> Result<String, ApiError> {
+    let mut buffer = Vec::new();
+    let mut chunk = [0u8; 4096];
+
+    let headers_end = loop {
+        if let Some(pos) = find_subslice(&buffer, b"\r\n\r\n") {
+            break pos;
+        }
+        if buffer.len() > MAX_HEADER_BYTES {
+            return Err(ApiError::BadRequest("request headers too large".to_string()));
+        }
+
+        let n = stream
+            .read(&mut chunk)
+            .map_err(|_| ApiError::BadRequest("failed to read request".to_string()))?;
+        if n == 0 {
+            return Ok(String::from_utf8_lossy(&buffer).to_string());
+        }
+        buffer.extend_from_slice(&chunk[..n]);
+    };
+
+    let headers = String::from_utf8_lossy(&buffer[..headers_end]).to_string();
+    let content_length: usize = headers
+        .lines()
+        .find_map(|line| {
+            let (name, value) = line.split_once(':')?;
+            name.trim().eq_ignore_ascii_case("Content-Length").then(|| value.trim())
+        })
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(0);
+
+    if content_length > *MAX_BODY_BYTES {
+        return Err(ApiError::PayloadTooLarge);
+    }
+
+    let body_start = headers_end + 4;
+    while buffer.len() < body_start + content_length {
+        let n = stream
+            .read(&mut chunk)
+            .map_err(|_| ApiError::BadRequest("failed to read request body".to_string()))?;
+        if n == 0 {
+            break;
         }
-        Err(e) => eprintln!("Unable to read stream: {e}"),
+        buffer.extend_from_slice(&chunk[..n]);
     }
+
+    Ok(String::from_utf8_lossy(&buffer).to_string())
 }
 
-// handle POST /users
-fn handle_post_request(request: &str) -> (String, String) {
-    match (get_user_request_body(request), Client::connect(DB_URL.as_str(), NoTls)) {
-        (Ok(user), Ok(mut client)) => {
-            // RETURNING id so we can respond with created user
-            match client.query_one(
-                "INSERT INTO users (name, email) VALUES ($1, $2) RETURNING id, name, email",
-                &[&user.name, &user.email],
-            ) {
-                Ok(row) => {
-                    let user = User {
-                        id: row.get(0),
-                        name: row.get(1),
-                        email: row.get(2),
-                    };
-                    (OK_RESPONSE.to_string(), serde_json::to_string(&user).unwrap_or_else(|_| "{}".to_string()))
-                }
-                Err(_) => (INTERNAL_ERROR.to_string(), "Internal error".to_string()),
-            }
+fn find_subslice(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack.windows(needle.len()).position(|window| window == needle)
+}
+
+// dispatch to the public auth routes, or authenticate and dispatch to the user CRUD routes
+fn route_request(request: &str) -> Result<(String, String), ApiError> {
+    match request {
+        r if r.starts_with("POST /auth/register") => auth::handle_register_request(r),
+        r if r.starts_with("POST /auth/login") => auth::handle_login_request(r),
+        r if r.starts_with("POST /users")
+            || r.starts_with("GET /users")
+            || r.starts_with("PUT /users/")
+            || r.starts_with("DELETE /users/") =>
+        {
+            let auth_user_id = auth::authenticate_request(r).ok_or(ApiError::Unauthorized)?;
+            dispatch_user_request(r, auth_user_id)
         }
-        _ => (INTERNAL_ERROR.to_string(), "Internal error".to_string()),
+        _ => Err(ApiError::NotFound),
     }
 }
 
+// dispatch an authenticated request to the matching user CRUD handler
+fn dispatch_user_request(request: &str, auth_user_id: i32) -> Result<(String, String), ApiError> {
+    match request {
+        r if r.starts_with("POST /users/") && r.contains("/roles") => {
+            rbac::handle_assign_role_request(r, auth_user_id)
+        }
+        r if r.starts_with("DELETE /users/") && r.contains("/roles/") => {
+            rbac::handle_revoke_role_request(r, auth_user_id)
+        }
+        r if r.starts_with("POST /users") => handle_post_request(r, auth_user_id),
+        r if r.starts_with("GET /users/") => handle_get_request(r, auth_user_id),
+        r if r.starts_with("GET /users") => handle_get_all_request(r, auth_user_id),
+        r if r.starts_with("PUT /users/") => handle_put_request(r, auth_user_id),
+        r if r.starts_with("DELETE /users/") => handle_delete_request(r, auth_user_id),
+        _ => Err(ApiError::NotFound),
+    }
+}
+
+// handle POST /users
+fn handle_post_request(request: &str, _auth_user_id: i32) -> Result<(String, String), ApiError> {
+    let user = get_user_request_body(request)
+        .map_err(|_| ApiError::BadRequest("invalid request body".to_string()))?;
+    user.validate().map_err(ApiError::Validation)?;
+
+    let password_hash = auth::hash_password(&user.password).map_err(|_| ApiError::Database)?;
+    let mut client = POOL.get()?;
+    // RETURNING id so we can respond with created user
+    let row = client.query_one(
+        "INSERT INTO users (name, email, password, attributes) VALUES ($1, $2, $3, $4) RETURNING id, name, email, password, attributes",
+        &[&user.name, &user.email, &password_hash, &user.attributes],
+    )?;
+    let user = User {
+        id: row.get(0),
+        name: row.get(1),
+        email: row.get(2),
+        password: row.get(3),
+        attributes: row.get(4),
+    };
+    Ok((OK_RESPONSE.to_string(), serde_json::to_string(&user).unwrap_or_else(|_| "{}".to_string())))
+}
+
 // handle GET /users/{id}
-fn handle_get_request(request: &str) -> (String, String) {
-    match (get_id(request).parse::<i32>(), Client::connect(DB_URL.as_str(), NoTls)) {
-        (Ok(id), Ok(mut client)) => match client.query_opt(
-            "SELECT id, name, email FROM users WHERE id = $1",
+fn handle_get_request(request: &str, _auth_user_id: i32) -> Result<(String, String), ApiError> {
+    let id = get_id(request)
+        .parse::<i32>()
+        .map_err(|_| ApiError::BadRequest("invalid id".to_string()))?;
+    let mut client = POOL.get()?;
+    let row = client
+        .query_opt(
+            "SELECT id, name, email, password, attributes FROM users WHERE id = $1",
             &[&id],
-        ) {
-            Ok(Some(row)) => {
-                let user = User {
-                    id: row.get(0),
-                    name: row.get(1),
-                    email: row.get(2),
-                };
-                (OK_RESPONSE.to_string(), serde_json::to_string(&user).unwrap_or_else(|_| "{}".to_string()))
-            }
-            Ok(None) => (NOT_FOUND.to_string(), "User not found".to_string()),
-            Err(_) => (INTERNAL_ERROR.to_string(), "Internal error".to_string()),
-        },
-        _ => (INTERNAL_ERROR.to_string(), "Internal error".to_string()),
-    }
-}
-
-// handle GET /users
-fn handle_get_all_request(_request: &str) -> (String, String) {
-    match Client::connect(DB_URL.as_str(), NoTls) {
-        Ok(mut client) => match client.query("SELECT id, name, email FROM users", &[]) {
-            Ok(rows) => {
-                let users: Vec<User> = rows
-                    .into_iter()
-                    .map(|row| User {
-                        id: row.get(0),
-                        name: row.get(1),
-                        email: row.get(2),
-                    })
-                    .collect();
-                (OK_RESPONSE.to_string(), serde_json::to_string(&users).unwrap_or_else(|_| "[]".to_string()))
-            }
-            Err(_) => (INTERNAL_ERROR.to_string(), "Internal error".to_string()),
-        },
-        _ => (INTERNAL_ERROR.to_string(), "Internal error".to_string()),
+        )?
+        .ok_or(ApiError::NotFound)?;
+    let user = User {
+        id: row.get(0),
+        name: row.get(1),
+        email: row.get(2),
+        password: row.get(3),
+        attributes: row.get(4),
+    };
+    Ok((OK_RESPONSE.to_string(), serde_json::to_string(&user).unwrap_or_else(|_| "{}".to_string())))
+}
+
+// handle GET /users?limit=&offset=&sort=&order=&name=&email=
+fn handle_get_all_request(request: &str, auth_user_id: i32) -> Result<(String, String), ApiError> {
+    if !rbac::has_permission(auth_user_id, rbac::USER_MANAGEMENT) {
+        return Err(ApiError::Forbidden);
+    }
+
+    let query = parse_query_string(request);
+    let param = |key: &str| query.iter().find(|(k, _)| k == key).map(|(_, v)| v.as_str());
+
+    let limit: i64 = match param("limit") {
+        Some(v) => v.parse().map_err(|_| ApiError::BadRequest("limit must be an integer".to_string()))?,
+        None => DEFAULT_LIMIT,
+    };
+    if limit <= 0 || limit > MAX_LIMIT {
+        return Err(ApiError::BadRequest(format!("limit must be between 1 and {MAX_LIMIT}")));
+    }
+
+    let offset: i64 = match param("offset") {
+        Some(v) => v.parse().map_err(|_| ApiError::BadRequest("offset must be an integer".to_string()))?,
+        None => 0,
+    };
+    if offset < 0 {
+        return Err(ApiError::BadRequest("offset must not be negative".to_string()));
     }
+
+    let sort = param("sort").unwrap_or("id");
+    if !SORTABLE_COLUMNS.contains(&sort) {
+        return Err(ApiError::BadRequest(format!(
+            "sort must be one of: {}",
+            SORTABLE_COLUMNS.join(", ")
+        )));
+    }
+
+    let order = match param("order").unwrap_or("asc").to_lowercase().as_str() {
+        "asc" => "ASC",
+        "desc" => "DESC",
+        _ => return Err(ApiError::BadRequest("order must be asc or desc".to_string())),
+    };
+
+    let name_pattern = param("name").map(|v| format!("%{}%", escape_like(v)));
+    let email_pattern = param("email").map(|v| format!("%{}%", escape_like(v)));
+
+    let mut where_clauses: Vec<String> = Vec::new();
+    let mut params: Vec<&(dyn ToSql + Sync)> = Vec::new();
+    if let Some(ref pattern) = name_pattern {
+        where_clauses.push(format!("name ILIKE ${}", params.len() + 1));
+        params.push(pattern);
+    }
+    if let Some(ref pattern) = email_pattern {
+        where_clauses.push(format!("email ILIKE ${}", params.len() + 1));
+        params.push(pattern);
+    }
+    let where_sql = if where_clauses.is_empty() {
+        String::new()
+    } else {
+        format!("WHERE {}", where_clauses.join(" AND "))
+    };
+
+    let mut client = POOL.get()?;
+
+    let total: i64 = client
+        .query_one(&format!("SELECT COUNT(*) FROM users {where_sql}"), &params)?
+        .get(0);
+
+    let limit_idx = params.len() + 1;
+    let offset_idx = params.len() + 2;
+    params.push(&limit);
+    params.push(&offset);
+
+    let rows = client.query(
+        &format!(
+            "SELECT id, name, email, password, attributes FROM users {where_sql} ORDER BY {sort} {order} LIMIT ${limit_idx} OFFSET ${offset_idx}"
+        ),
+        &params,
+    )?;
+    let users: Vec<User> = rows
+        .into_iter()
+        .map(|row| User {
+            id: row.get(0),
+            name: row.get(1),
+            email: row.get(2),
+            password: row.get(3),
+            attributes: row.get(4),
+        })
+        .collect();
+
+    Ok((
+        OK_RESPONSE.to_string(),
+        serde_json::json!({ "users": users, "total": total }).to_string(),
+    ))
 }
 
 // handle PUT /users/{id}
-fn handle_put_request(request: &str) -> (String, String) {
-    match (
-        get_id(request).parse::<i32>(),
-        get_user_request_body(request),
-        Client::connect(DB_URL.as_str(), NoTls),
-    ) {
-        (Ok(id), Ok(user), Ok(mut client)) => {
-            match client.execute(
-                "UPDATE users SET name = $1, email = $2 WHERE id = $3",
-                &[&user.name, &user.email, &id],
-            ) {
-                Ok(n) if n > 0 => (OK_RESPONSE.to_string(), "User updated".to_string()),
-                Ok(_) => (NOT_FOUND.to_string(), "User not found".to_string()),
-                Err(_) => (INTERNAL_ERROR.to_string(), "Internal error".to_string()),
-            }
+fn handle_put_request(request: &str, auth_user_id: i32) -> Result<(String, String), ApiError> {
+    let id = get_id(request)
+        .parse::<i32>()
+        .map_err(|_| ApiError::BadRequest("invalid id".to_string()))?;
+    let user: UserUpdate = serde_json::from_str(request.split("\r\n\r\n").last().unwrap_or_default())
+        .map_err(|_| ApiError::BadRequest("invalid request body".to_string()))?;
+
+    if id != auth_user_id && !rbac::has_permission(auth_user_id, rbac::USER_MANAGEMENT) {
+        return Err(ApiError::Forbidden);
+    }
+    user.validate().map_err(ApiError::Validation)?;
+
+    let mut client = POOL.get()?;
+    // only re-hash/overwrite the password when one was supplied, and only touch
+    // attributes when the field was present, so an update omitting either one
+    // leaves the stored value in place
+    let n = match &user.password {
+        Some(password) => {
+            let password_hash = auth::hash_password(password).map_err(|_| ApiError::Database)?;
+            client.execute(
+                "UPDATE users SET name = $1, email = $2, password = $3, attributes = COALESCE($4, attributes) WHERE id = $5",
+                &[&user.name, &user.email, &password_hash, &user.attributes, &id],
+            )?
         }
-        _ => (INTERNAL_ERROR.to_string(), "Internal error".to_string()),
+        None => client.execute(
+            "UPDATE users SET name = $1, email = $2, attributes = COALESCE($3, attributes) WHERE id = $4",
+            &[&user.name, &user.email, &user.attributes, &id],
+        )?,
+    };
+    if n == 0 {
+        return Err(ApiError::NotFound);
     }
+    Ok((OK_RESPONSE.to_string(), "User updated".to_string()))
 }
 
 // handle DELETE /users/{id}
-fn handle_delete_request(request: &str) -> (String, String) {
-    match (get_id(request).parse::<i32>(), Client::connect(DB_URL.as_str(), NoTls)) {
-        (Ok(id), Ok(mut client)) => match client.execute("DELETE FROM users WHERE id = $1", &[&id]) {
-            Ok(0) => (NOT_FOUND.to_string(), "User not found".to_string()),
-            Ok(_) => (OK_RESPONSE.to_string(), "User deleted".to_string()),
-            Err(_) => (INTERNAL_ERROR.to_string(), "Internal error".to_string()),
-        },
-        _ => (INTERNAL_ERROR.to_string(), "Internal error".to_string()),
+fn handle_delete_request(request: &str, auth_user_id: i32) -> Result<(String, String), ApiError> {
+    let id = get_id(request)
+        .parse::<i32>()
+        .map_err(|_| ApiError::BadRequest("invalid id".to_string()))?;
+
+    if id != auth_user_id && !rbac::has_permission(auth_user_id, rbac::USER_MANAGEMENT) {
+        return Err(ApiError::Forbidden);
+    }
+
+    let mut client = POOL.get()?;
+    let n = client.execute("DELETE FROM users WHERE id = $1", &[&id])?;
+    if n == 0 {
+        return Err(ApiError::NotFound);
     }
+    Ok((OK_RESPONSE.to_string(), "User deleted".to_string()))
 }
 
 // db setup
@@ -178,13 +420,92 @@ fn set_database() -> Result<(), PostgresError> {
         CREATE TABLE IF NOT EXISTS users (
             id SERIAL PRIMARY KEY,
             name TEXT NOT NULL,
-            email TEXT NOT NULL UNIQUE
+            email TEXT NOT NULL UNIQUE,
+            password TEXT NOT NULL,
+            attributes JSONB NOT NULL DEFAULT 'null'
+        );
+
+        CREATE TABLE IF NOT EXISTS roles (
+            id SERIAL PRIMARY KEY,
+            name TEXT NOT NULL UNIQUE
+        );
+
+        CREATE TABLE IF NOT EXISTS permissions (
+            id SERIAL PRIMARY KEY,
+            name TEXT NOT NULL UNIQUE
+        );
+
+        CREATE TABLE IF NOT EXISTS role_permissions (
+            role_id INTEGER NOT NULL REFERENCES roles(id),
+            permission_id INTEGER NOT NULL REFERENCES permissions(id),
+            PRIMARY KEY (role_id, permission_id)
+        );
+
+        CREATE TABLE IF NOT EXISTS user_roles (
+            user_id INTEGER NOT NULL REFERENCES users(id),
+            role_id INTEGER NOT NULL REFERENCES roles(id),
+            PRIMARY KEY (user_id, role_id)
         );
         ",
     )?;
     Ok(())
 }
 
+// parse the "a=1&b=2" query string off a request line's path, e.g. "GET /users?a=1&b=2 HTTP/1.1",
+// percent-decoding each key/value so clients can pass reserved characters (spaces, "&", "%", ...)
+fn parse_query_string(request: &str) -> Vec<(String, String)> {
+    let line = request.lines().next().unwrap_or_default();
+    let query = match line.split_once('?') {
+        Some((_, query)) => query,
+        None => return Vec::new(),
+    };
+    let query = query.split_whitespace().next().unwrap_or_default();
+
+    query
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .filter_map(|pair| pair.split_once('='))
+        .map(|(k, v)| (percent_decode(k), percent_decode(v)))
+        .collect()
+}
+
+// decode "%XX" escapes in a query-string component; bytes that don't form a valid
+// escape are passed through unchanged
+fn percent_decode(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%'
+            && i + 2 < bytes.len()
+            && bytes[i + 1].is_ascii_hexdigit()
+            && bytes[i + 2].is_ascii_hexdigit()
+        {
+            if let Ok(byte) = u8::from_str_radix(&value[i + 1..i + 3], 16) {
+                out.push(byte);
+                i += 3;
+                continue;
+            }
+        }
+        out.push(bytes[i]);
+        i += 1;
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+// escape ILIKE metacharacters so a filter value containing "%" or "_" is matched
+// literally rather than as a wildcard
+fn escape_like(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        if c == '\\' || c == '%' || c == '_' {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
 // Get id from request URL
 fn get_id(request: &str) -> &str {
     request
@@ -200,3 +521,45 @@ fn get_id(request: &str) -> &str {
 fn get_user_request_body(request: &str) -> Result<User, serde_json::Error> {
     serde_json::from_str(request.split("\r\n\r\n").last().unwrap_or_default())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_pairs_off_the_request_line() {
+        let request = "GET /users?limit=5&sort=name HTTP/1.1\r\nHost: localhost\r\n\r\n";
+        let pairs = parse_query_string(request);
+        assert_eq!(
+            pairs,
+            vec![
+                ("limit".to_string(), "5".to_string()),
+                ("sort".to_string(), "name".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn returns_empty_without_a_query_string() {
+        assert_eq!(parse_query_string("GET /users HTTP/1.1\r\n\r\n"), Vec::new());
+    }
+
+    #[test]
+    fn percent_decodes_keys_and_values() {
+        let request = "GET /users?name=Jane%20Doe&email=a%40b.com HTTP/1.1\r\n\r\n";
+        let pairs = parse_query_string(request);
+        assert_eq!(
+            pairs,
+            vec![
+                ("name".to_string(), "Jane Doe".to_string()),
+                ("email".to_string(), "a@b.com".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn escapes_like_metacharacters() {
+        assert_eq!(escape_like("50%_off"), "50\\%\\_off");
+        assert_eq!(escape_like("plain"), "plain");
+    }
+}
@@ -0,0 +1,182 @@
+use crate::error::ApiError;
+use crate::{auth, OK_RESPONSE, POOL};
+use serde::Deserialize;
+use std::env;
+
+const ADMIN_ROLE: &str = "ADMIN";
+pub const USER_MANAGEMENT: &str = "USER_MANAGEMENT";
+
+#[derive(Deserialize)]
+struct AssignRoleSchema {
+    role: String,
+}
+
+// create the ADMIN role, the USER_MANAGEMENT permission, and the bootstrap admin account
+// seeded from ADMIN_EMAIL/ADMIN_PASSWORD, if they don't already exist
+pub fn bootstrap() {
+    let mut client = match POOL.get() {
+        Ok(client) => client,
+        Err(e) => {
+            eprintln!("Error getting a connection to seed RBAC defaults: {e}");
+            return;
+        }
+    };
+
+    if let Err(e) = seed_default_role_and_permission(&mut client) {
+        eprintln!("Error seeding default role/permission: {e}");
+    }
+
+    if let Err(e) = seed_admin_user(&mut client) {
+        eprintln!("Error seeding bootstrap admin account: {e}");
+    }
+}
+
+fn seed_default_role_and_permission(client: &mut crate::DbConn) -> Result<(), postgres::Error> {
+    client.execute(
+        "INSERT INTO roles (name) VALUES ($1) ON CONFLICT (name) DO NOTHING",
+        &[&ADMIN_ROLE],
+    )?;
+    client.execute(
+        "INSERT INTO permissions (name) VALUES ($1) ON CONFLICT (name) DO NOTHING",
+        &[&USER_MANAGEMENT],
+    )?;
+    client.execute(
+        "INSERT INTO role_permissions (role_id, permission_id)
+         SELECT r.id, p.id FROM roles r, permissions p
+         WHERE r.name = $1 AND p.name = $2
+         ON CONFLICT DO NOTHING",
+        &[&ADMIN_ROLE, &USER_MANAGEMENT],
+    )?;
+    Ok(())
+}
+
+fn seed_admin_user(client: &mut crate::DbConn) -> Result<(), postgres::Error> {
+    let (admin_email, admin_password) = match (env::var("ADMIN_EMAIL"), env::var("ADMIN_PASSWORD")) {
+        (Ok(email), Ok(password)) => (email, password),
+        _ => {
+            eprintln!("ADMIN_EMAIL/ADMIN_PASSWORD not set; skipping bootstrap admin seed");
+            return Ok(());
+        }
+    };
+
+    let admin_id: i32 = match client.query_opt("SELECT id FROM users WHERE email = $1", &[&admin_email])? {
+        Some(row) => row.get(0),
+        None => {
+            let password_hash = match auth::hash_password(&admin_password) {
+                Ok(hash) => hash,
+                Err(e) => {
+                    eprintln!("failed to hash admin password: {e}");
+                    return Ok(());
+                }
+            };
+            let row = client.query_one(
+                "INSERT INTO users (name, email, password) VALUES ($1, $2, $3) RETURNING id",
+                &[&"Admin", &admin_email, &password_hash],
+            )?;
+            row.get(0)
+        }
+    };
+
+    client.execute(
+        "INSERT INTO user_roles (user_id, role_id)
+         SELECT $1, r.id FROM roles r WHERE r.name = $2
+         ON CONFLICT DO NOTHING",
+        &[&admin_id, &ADMIN_ROLE],
+    )?;
+    Ok(())
+}
+
+// does the given user hold a role granting `permission`?
+pub fn has_permission(user_id: i32, permission: &str) -> bool {
+    let mut client = match POOL.get() {
+        Ok(client) => client,
+        Err(_) => return false,
+    };
+
+    client
+        .query_opt(
+            "SELECT 1 FROM user_roles ur
+             JOIN role_permissions rp ON rp.role_id = ur.role_id
+             JOIN permissions p ON p.id = rp.permission_id
+             WHERE ur.user_id = $1 AND p.name = $2",
+            &[&user_id, &permission],
+        )
+        .map(|row| row.is_some())
+        .unwrap_or(false)
+}
+
+// handle POST /users/{id}/roles
+pub fn handle_assign_role_request(request: &str, auth_user_id: i32) -> Result<(String, String), ApiError> {
+    if !has_permission(auth_user_id, USER_MANAGEMENT) {
+        return Err(ApiError::Forbidden);
+    }
+
+    let target_id = crate::get_id(request)
+        .parse::<i32>()
+        .map_err(|_| ApiError::BadRequest("invalid id".to_string()))?;
+    let body = get_request_body(request)
+        .map_err(|_| ApiError::BadRequest("invalid request body".to_string()))?;
+
+    let mut client = POOL.get()?;
+    client.execute(
+        "INSERT INTO user_roles (user_id, role_id)
+         SELECT $1, r.id FROM roles r WHERE r.name = $2
+         ON CONFLICT DO NOTHING",
+        &[&target_id, &body.role],
+    )?;
+    Ok((OK_RESPONSE.to_string(), "Role assigned".to_string()))
+}
+
+// handle DELETE /users/{id}/roles/{role}
+pub fn handle_revoke_role_request(request: &str, auth_user_id: i32) -> Result<(String, String), ApiError> {
+    if !has_permission(auth_user_id, USER_MANAGEMENT) {
+        return Err(ApiError::Forbidden);
+    }
+
+    let target_id = crate::get_id(request)
+        .parse::<i32>()
+        .map_err(|_| ApiError::BadRequest("invalid id".to_string()))?;
+    let role = get_role(request);
+    if role.is_empty() {
+        return Err(ApiError::BadRequest("missing role".to_string()));
+    }
+
+    let mut client = POOL.get()?;
+    client.execute(
+        "DELETE FROM user_roles
+         WHERE user_id = $1 AND role_id = (SELECT id FROM roles WHERE name = $2)",
+        &[&target_id, &role],
+    )?;
+    Ok((OK_RESPONSE.to_string(), "Role revoked".to_string()))
+}
+
+fn get_request_body(request: &str) -> Result<AssignRoleSchema, serde_json::Error> {
+    serde_json::from_str(request.split("\r\n\r\n").last().unwrap_or_default())
+}
+
+// role name trailing "/users/{id}/roles/{role}"
+fn get_role(request: &str) -> &str {
+    request
+        .split('/')
+        .nth(4)
+        .unwrap_or_default()
+        .split_whitespace()
+        .next()
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn extracts_role_from_the_request_line() {
+        assert_eq!(get_role("DELETE /users/1/roles/ADMIN HTTP/1.1"), "ADMIN");
+    }
+
+    #[test]
+    fn returns_empty_when_role_is_missing() {
+        assert_eq!(get_role("DELETE /users/1/roles/"), "");
+        assert_eq!(get_role("DELETE /users/1/roles"), "");
+    }
+}
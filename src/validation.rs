@@ -0,0 +1,96 @@
+// server-side validation, run before any SQL touches user-supplied input
+pub trait Validate {
+    fn validate(&self) -> Result<(), Vec<String>>;
+}
+
+impl Validate for crate::User {
+    fn validate(&self) -> Result<(), Vec<String>> {
+        validate_name_email(&self.name, &self.email)
+    }
+}
+
+impl Validate for crate::UserUpdate {
+    fn validate(&self) -> Result<(), Vec<String>> {
+        validate_name_email(&self.name, &self.email)
+    }
+}
+
+pub(crate) fn validate_name_email(name: &str, email: &str) -> Result<(), Vec<String>> {
+    let mut errors = Vec::new();
+
+    if name.chars().count() == 0 || name.chars().count() > 50 {
+        errors.push("name must be between 1 and 50 characters".to_string());
+    }
+
+    if email.chars().count() == 0 || email.chars().count() > 255 {
+        errors.push("email must be between 1 and 255 characters".to_string());
+    } else if !is_valid_email(email) {
+        errors.push("email must be a valid email address".to_string());
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+// a basic shape check (local@domain.tld), not a full RFC 5322 validator
+fn is_valid_email(email: &str) -> bool {
+    match email.split_once('@') {
+        Some((local, domain)) => {
+            !local.is_empty()
+                && !domain.is_empty()
+                && !domain.contains('@')
+                && domain.contains('.')
+                && !domain.starts_with('.')
+                && !domain.ends_with('.')
+        }
+        None => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accepts_simple_addresses() {
+        assert!(is_valid_email("user@example.com"));
+    }
+
+    #[test]
+    fn rejects_missing_at_sign() {
+        assert!(!is_valid_email("user.example.com"));
+    }
+
+    #[test]
+    fn rejects_empty_local_part() {
+        assert!(!is_valid_email("@example.com"));
+    }
+
+    #[test]
+    fn rejects_domain_without_a_dot() {
+        assert!(!is_valid_email("user@example"));
+    }
+
+    #[test]
+    fn rejects_domain_with_leading_or_trailing_dot() {
+        assert!(!is_valid_email("user@.example.com"));
+        assert!(!is_valid_email("user@example.com."));
+    }
+
+    #[test]
+    fn rejects_a_domain_containing_another_at_sign() {
+        assert!(!is_valid_email("user@@.com"));
+        assert!(!is_valid_email("user@example.com@evil.com"));
+    }
+
+    #[test]
+    fn counts_name_length_in_chars_not_bytes() {
+        let name = "ä".repeat(50);
+        assert_eq!(name.chars().count(), 50);
+        assert!(name.len() > 50);
+        assert_eq!(validate_name_email(&name, "user@example.com"), Ok(()));
+    }
+}